@@ -1,5 +1,9 @@
 use std::cmp::PartialEq;
 
+use js_sys::Math;
+
+use crate::pattern::Pattern;
+
 /// A storage with two buffers for arbitary type.
 /// 
 /// This is useful for generating next generation world while referring to the
@@ -49,17 +53,28 @@ pub enum Cell {
     Dead,
 }
 
+/// The boundary behavior used when counting the neighbours of edge cells.
+#[derive(PartialEq, Copy, Clone)]
+pub enum Topology {
+    /// Cells past the edge of the world are simply absent.
+    Bounded,
+    /// Cells past the edge of the world wrap around to the opposite edge.
+    Toroidal,
+}
+
 /// The Game of Life world.
 pub struct World {
     stride: i32,
     cells: DoubleBuffer<Vec<Cell>>,
+    dirty: Vec<(i32, i32, Cell)>,
+    topology: Topology,
 }
 
 impl World {
-    /// Create a new world with given size.
-    pub fn new(width: i32, height: i32) -> World {
+    /// Create a new world with given size and boundary topology.
+    pub fn new(width: i32, height: i32, topology: Topology) -> World {
         let cells: DoubleBuffer<Vec<Cell>> = DoubleBuffer::new(|| {
-            let length = (width * height) as usize; 
+            let length = (width * height) as usize;
             let mut vec = Vec::with_capacity(length);
             for _ in 0..length {
                 vec.push(Cell::Dead);
@@ -70,6 +85,8 @@ impl World {
         return World {
             stride: width,
             cells: cells,
+            dirty: Vec::new(),
+            topology: topology,
         };
     }
 
@@ -99,16 +116,23 @@ impl World {
         let mut count = 0;
         for x_dir in dir.iter() {
             for y_dir in dir.iter() {
-                let dest_x = x + x_dir;
-                let dest_y = y + y_dir;
-                if dest_x < 0 || dest_y < 0 || (dest_x == x && dest_y == y) {
+                if *x_dir == 0 && *y_dir == 0 {
                     continue;
                 }
 
+                let raw_x = x + x_dir;
+                let raw_y = y + y_dir;
+
+                let (dest_x, dest_y) = if self.topology == Topology::Toroidal {
+                    (raw_x.rem_euclid(self.width()), raw_y.rem_euclid(self.height()))
+                } else {
+                    if raw_x < 0 || raw_x >= self.width() || raw_y < 0 || raw_y >= self.height() {
+                        continue;
+                    }
+                    (raw_x, raw_y)
+                };
+
                 let dest_index = self.index(dest_x, dest_y);
-                if dest_index >= self.cells.front_ref().len() {
-                    continue;
-                }
                 if self.cells.front_ref()[dest_index] == Cell::Alive {
                     count += 1;
                 }
@@ -119,15 +143,18 @@ impl World {
 
     /// Generate the next generation.
     pub fn next_gen(&mut self) {
+        self.dirty.clear();
+
         for col in 0..(self.height()) {
             for row in 0..(self.width()) {
                 let index = self.index(row, col);
                 let neighbour_count = self.neighbour_count(row, col);
+                let current_cell = self.cells.front_ref()[index];
 
                 // The rules of "Conway's Game of Life":
                 // https://en.wikipedia.org/wiki/Conway%27s_Game_of_Life
                 let next_gen_cell =
-                if self.cells.front_ref()[index] == Cell::Alive {
+                if current_cell == Cell::Alive {
                     match neighbour_count {
                         0..2  => Cell::Dead,
                         2..=3 => Cell::Alive,
@@ -141,6 +168,10 @@ impl World {
                     }
                 };
 
+                if next_gen_cell != current_cell {
+                    self.dirty.push((row, col, next_gen_cell));
+                }
+
                 // We fill the next generation cells to the back store.
                 let back = self.cells.back_mut();
                 back[index] = next_gen_cell;
@@ -151,6 +182,45 @@ impl World {
         self.cells.swap();
     }
 
+    /// Sets every cell to dead.
+    pub fn clear(&mut self) {
+        for col in 0..(self.height()) {
+            for row in 0..(self.width()) {
+                self.set_cell(row, col, Cell::Dead);
+            }
+        }
+    }
+
+    /// Reseeds the world, setting each cell alive with the given probability.
+    pub fn randomize(&mut self, density: f64) {
+        for col in 0..(self.height()) {
+            for row in 0..(self.width()) {
+                let cell = if Math::random() < density { Cell::Alive } else { Cell::Dead };
+                self.set_cell(row, col, cell);
+            }
+        }
+    }
+
+    /// Stamps the given pattern into the world, offsetting its live cells by
+    /// `(origin_x, origin_y)`. Cells that land outside the world are skipped.
+    pub fn apply_pattern(&mut self, pattern: &Pattern, origin_x: i32, origin_y: i32) {
+        for &(x, y) in pattern.live_cells() {
+            let dest_x = origin_x + x;
+            let dest_y = origin_y + y;
+            if dest_x < 0 || dest_x >= self.width() || dest_y < 0 || dest_y >= self.height() {
+                continue;
+            }
+
+            self.set_cell(dest_x, dest_y, Cell::Alive);
+        }
+    }
+
+    /// Returns the cells that flipped state (alive <-> dead) during the most
+    /// recent `next_gen()` call, as `(x, y, Cell)` tuples.
+    pub fn dirty_cells(&self) -> impl Iterator<Item = &(i32, i32, Cell)> {
+        return self.dirty.iter();
+    }
+
     /// Returns the index of cell vector for the cell at the given point.
     fn index(&self, x: i32, y: i32) -> usize {
         return (self.stride * y + x) as usize;
@@ -7,16 +7,42 @@ use web_sys::{console, window};
 
 use crate::ui;
 use crate::engine;
+use crate::pattern;
 
 struct UiResponder {
     state: State,
 }
 
+/// The DOM `PointerEvent.button` value for the secondary (typically right)
+/// pointer button, used to trigger erase mode.
+const SECONDARY_BUTTON: i16 = 2;
+
+/// The fraction of cells set alive by the "randomize" key binding.
+const DEFAULT_RANDOMIZE_DENSITY: f64 = 0.3;
+
+/// A glider, stamped at the center of the world by the "g" key binding.
+const GLIDER_RLE: &str = "x = 3, y = 3\nbo$2bo$3o!\n";
+
+/// A blinker, stamped at the center of the world by the "l" key binding.
+const BLINKER_LIFE_106: &str = "#Life 1.06\n0 0\n1 0\n2 0\n";
+
+/// The amount the "+"/"-" key bindings change the tick interval by, in
+/// milliseconds.
+const UPDATE_INTERVAL_STEP: i32 = 10;
+
+/// The fastest the simulation is allowed to auto-advance, in milliseconds.
+const MIN_UPDATE_INTERVAL: i32 = 10;
+
 impl ui::Responder for UiResponder {
     fn on_mouse_down(&self, point: ui::Point) {
         js_log!("on_mouse_down: {:?}", point);
         let mut state_inner = self.state.get_inner();
         state_inner.is_mouse_down = true;
+        state_inner.draw_cell_state = if point.button == SECONDARY_BUTTON {
+            engine::Cell::Dead
+        } else {
+            engine::Cell::Alive
+        };
 
         // TODO: Maybe we should not use inner here.
         drop(state_inner);
@@ -37,6 +63,50 @@ impl ui::Responder for UiResponder {
         js_log!("on_mouse_up");
         self.state.get_inner().is_mouse_down = false;
     }
+
+    fn on_key_down(&self, key: String) {
+        match key.as_str() {
+            " " => {
+                if self.state.is_running() {
+                    self.state.pause();
+                } else {
+                    self.state.resume();
+                }
+            }
+            "s" | "S" => self.state.step(),
+            "c" | "C" => self.state.clear(),
+            "r" | "R" => self.state.randomize(DEFAULT_RANDOMIZE_DENSITY),
+            "g" | "G" => {
+                let glider = pattern::Pattern::parse_rle(GLIDER_RLE)
+                    .expect("the embedded glider pattern is valid RLE");
+                self.state.stamp_pattern_centered(&glider);
+            }
+            "l" | "L" => {
+                let blinker = pattern::Pattern::parse_life_106(BLINKER_LIFE_106)
+                    .expect("the embedded blinker pattern is valid Life 1.06");
+                self.state.stamp_pattern_centered(&blinker);
+            }
+            "e" | "E" => {
+                js_log!("{}", self.state.export_rle());
+            }
+            "+" | "=" => {
+                let interval = (self.state.update_interval() - UPDATE_INTERVAL_STEP)
+                    .max(MIN_UPDATE_INTERVAL);
+                self.state.set_update_interval(interval);
+            }
+            "-" | "_" => {
+                let interval = self.state.update_interval() + UPDATE_INTERVAL_STEP;
+                self.state.set_update_interval(interval);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_resize(&self) {
+        js_log!("on_resize");
+        self.state.get_inner().canvas.handle_resize();
+        self.state.full_redraw();
+    }
 }
 
 pub struct Config {
@@ -52,6 +122,10 @@ struct StateInner {
     world: engine::World,
     config: Config,
     is_mouse_down: bool,
+    /// The cell state applied by the current/next drag, toggled between
+    /// [`engine::Cell::Alive`] (primary button) and [`engine::Cell::Dead`]
+    /// (secondary button, i.e. erase mode).
+    draw_cell_state: engine::Cell,
     timer_closure: Option<Box<dyn Drop>>,
     timer_id: i32,
 }
@@ -64,6 +138,7 @@ impl State {
                 world: world,
                 config: config,
                 is_mouse_down: false,
+                draw_cell_state: engine::Cell::Alive,
                 timer_closure: None,
                 timer_id: -1,
             })),
@@ -111,11 +186,76 @@ impl State {
         inner.timer_id = -1;
     }
 
-    fn put_cell(&self, x: i32, y: i32) {
-        self.get_inner().world.set_cell(x, y, engine::Cell::Alive);
+    /// Returns whether the simulation is currently auto-advancing.
+    pub fn is_running(&self) -> bool {
+        return self.get_inner().timer_closure.is_some();
+    }
+
+    /// Advances exactly one generation, regardless of whether the simulation
+    /// is currently running.
+    pub fn step(&self) {
+        self.get_inner().world.next_gen();
         self.update_canvas();
     }
 
+    /// Kills every cell and clears the canvas.
+    pub fn clear(&self) {
+        self.get_inner().world.clear();
+        self.full_redraw();
+    }
+
+    /// Reseeds the world with randomly alive cells and repaints.
+    pub fn randomize(&self, density: f64) {
+        self.get_inner().world.randomize(density);
+        self.full_redraw();
+    }
+
+    /// Stamps the given pattern into the world, centered, and repaints.
+    pub fn stamp_pattern_centered(&self, pattern: &pattern::Pattern) {
+        let inner = self.get_inner();
+        let origin_x = (inner.world.width() - pattern.width()) / 2;
+        let origin_y = (inner.world.height() - pattern.height()) / 2;
+        drop(inner);
+
+        self.get_inner().world.apply_pattern(pattern, origin_x, origin_y);
+        self.full_redraw();
+    }
+
+    /// Serializes the current generation as an RLE pattern string.
+    pub fn export_rle(&self) -> String {
+        return pattern::Pattern::to_rle(&self.get_inner().world);
+    }
+
+    /// Returns the current auto-advance period, in milliseconds.
+    pub fn update_interval(&self) -> i32 {
+        return self.get_inner().config.update_interval;
+    }
+
+    /// Changes the auto-advance period, restarting the timer if it's
+    /// currently running.
+    pub fn set_update_interval(&self, ms: i32) {
+        let was_running = self.is_running();
+        self.get_inner().config.update_interval = ms;
+
+        if was_running {
+            self.pause();
+            self.resume();
+        }
+    }
+
+    fn put_cell(&self, x: i32, y: i32) {
+        let inner = self.get_inner();
+        // A captured pointer can report points outside the canvas.
+        if x < 0 || x >= inner.world.width() || y < 0 || y >= inner.world.height() {
+            return;
+        }
+        inner.world.set_cell(x, y, inner.draw_cell_state);
+        match inner.draw_cell_state {
+            engine::Cell::Alive => inner.canvas.draw_cell(x, y),
+            engine::Cell::Dead => inner.canvas.erase_cell(x, y),
+        }
+    }
+
     fn tick(&self) {
         // js_log!("tick!");
 
@@ -123,7 +263,23 @@ impl State {
         self.update_canvas();
     }
 
+    /// Repaints only the cells that flipped in the last `next_gen()`, instead
+    /// of clearing and redrawing the whole canvas every tick.
     fn update_canvas(&self) {
+        let inner = self.get_inner();
+        for &(x, y, cell) in inner.world.dirty_cells() {
+            if cell == engine::Cell::Alive {
+                inner.canvas.draw_cell(x, y);
+            } else {
+                inner.canvas.erase_cell(x, y);
+            }
+        }
+    }
+
+    /// Clears the canvas and redraws every living cell in the world, used by
+    /// operations (clear/randomize/pattern stamping) that touch the grid
+    /// without a tracked diff, and after a resize.
+    fn full_redraw(&self) {
         let inner = self.get_inner();
         inner.canvas.clear();
         for col in 0..(inner.world.height()) {
@@ -5,12 +5,12 @@ mod utils;
 mod ui;
 mod game;
 mod engine;
+mod pattern;
 
 use std::mem;
 
 use wasm_bindgen::prelude::*;
 use web_sys;
-use js_sys::Math;
 
 #[wasm_bindgen]
 pub fn start() {
@@ -25,24 +25,17 @@ pub fn start() {
 
     let window = web_sys::window().expect("There must be a window instance");
     let document = window.document().expect("There must be a document instance");
-    let canvas = ui::Canvas::new(&document, rows, cols);
+    let canvas = ui::Canvas::new(&document, rows, cols, ui::ScaleMode::Fit);
 
-    let mut world = engine::World::new(rows, cols);
-    generate_initial_world(&mut world);
+    let world = engine::World::new(rows, cols, engine::Topology::Bounded);
 
     let state = game::State::new(canvas, world, config);
+    // Seed through the public API rather than the engine directly, so the
+    // initial population is actually painted (`randomize` triggers a full
+    // redraw; seeding the `World` before `State` exists would not).
+    state.randomize(0.3);
     state.resume();
 
     // TODO: We really should manage the memory correctly!
     mem::forget(state);
-}
-
-fn generate_initial_world(world: &mut engine::World) {
-    for col in 0..(world.height()) {
-        for row in 0..(world.width()) {
-            if Math::random() < 0.3 {
-                world.set_cell(row, col, engine::Cell::Alive);
-            }
-        }
-    }
 }
\ No newline at end of file
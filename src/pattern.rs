@@ -0,0 +1,214 @@
+//! Parsing and serialization of standard Game-of-Life pattern files (RLE and
+//! Life 1.06). Decoded patterns are stamped into a [`World`] by
+//! [`World::apply_pattern`].
+
+use crate::engine::{Cell, World};
+
+#[derive(Debug)]
+pub enum PatternError {
+    InvalidHeader,
+    InvalidBody,
+}
+
+/// A decoded pattern: the set of live cells relative to the pattern's own
+/// top-left origin, together with its bounding size.
+pub struct Pattern {
+    width: i32,
+    height: i32,
+    live_cells: Vec<(i32, i32)>,
+}
+
+impl Pattern {
+    pub fn width(&self) -> i32 {
+        return self.width;
+    }
+
+    pub fn height(&self) -> i32 {
+        return self.height;
+    }
+
+    /// Returns the coordinates of the pattern's live cells, relative to its
+    /// own top-left origin.
+    pub fn live_cells(&self) -> impl Iterator<Item = &(i32, i32)> {
+        return self.live_cells.iter();
+    }
+
+    /// Parses a pattern in RLE format: an `x = .., y = ..` header followed by
+    /// a run-length-encoded body, where a leading integer count repeats the
+    /// next tag (`b` = dead, `o` = alive, `$` = end of row, `!` = end of
+    /// pattern).
+    pub fn parse_rle(input: &str) -> Result<Pattern, PatternError> {
+        let mut header: Option<(i32, i32)> = None;
+        let mut body = String::new();
+
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if header.is_none() {
+                header = Some(parse_rle_header(line)?);
+                continue;
+            }
+            body.push_str(line);
+        }
+
+        let (width, height) = header.ok_or(PatternError::InvalidHeader)?;
+
+        let mut live_cells = Vec::new();
+        let mut x = 0;
+        let mut y = 0;
+        let mut count = 0;
+
+        for ch in body.chars() {
+            match ch {
+                '0'..='9' => {
+                    count = count * 10 + (ch as i32 - '0' as i32);
+                }
+                'b' | 'o' | '$' => {
+                    let run = if count == 0 { 1 } else { count };
+                    count = 0;
+                    match ch {
+                        'o' => {
+                            for _ in 0..run {
+                                live_cells.push((x, y));
+                                x += 1;
+                            }
+                        }
+                        'b' => x += run,
+                        _ => {
+                            y += run;
+                            x = 0;
+                        }
+                    }
+                }
+                '!' => break,
+                _ => return Err(PatternError::InvalidBody),
+            }
+        }
+
+        return Ok(Pattern {
+            width: width,
+            height: height,
+            live_cells: live_cells,
+        });
+    }
+
+    /// Parses a pattern in Life 1.06 format: a `#Life 1.06` header followed
+    /// by one `x y` signed-integer coordinate pair per living cell.
+    pub fn parse_life_106(input: &str) -> Result<Pattern, PatternError> {
+        let mut lines = input.lines();
+        match lines.next() {
+            Some(header) if header.trim().starts_with("#Life 1.06") => {}
+            _ => return Err(PatternError::InvalidHeader),
+        }
+
+        let mut live_cells = Vec::new();
+        let mut min_x = i32::max_value();
+        let mut min_y = i32::max_value();
+        let mut max_x = i32::min_value();
+        let mut max_y = i32::min_value();
+
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let x: i32 = parts.next()
+                .and_then(|v| v.parse().ok())
+                .ok_or(PatternError::InvalidBody)?;
+            let y: i32 = parts.next()
+                .and_then(|v| v.parse().ok())
+                .ok_or(PatternError::InvalidBody)?;
+
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+            live_cells.push((x, y));
+        }
+
+        if live_cells.is_empty() {
+            return Ok(Pattern { width: 0, height: 0, live_cells: live_cells });
+        }
+
+        // Normalize coordinates so the pattern's own origin is (0, 0).
+        for cell in live_cells.iter_mut() {
+            cell.0 -= min_x;
+            cell.1 -= min_y;
+        }
+
+        return Ok(Pattern {
+            width: max_x - min_x + 1,
+            height: max_y - min_y + 1,
+            live_cells: live_cells,
+        });
+    }
+
+    /// Serializes the current generation of the given world as an RLE
+    /// pattern.
+    pub fn to_rle(world: &World) -> String {
+        let width = world.width();
+        let height = world.height();
+
+        let mut body = String::new();
+        for y in 0..height {
+            let mut runs: Vec<(char, i32)> = Vec::new();
+            for x in 0..width {
+                let ch = if world.cell_at(x, y) == Cell::Alive { 'o' } else { 'b' };
+                match runs.last_mut() {
+                    Some((last_ch, count)) if *last_ch == ch => *count += 1,
+                    _ => runs.push((ch, 1)),
+                }
+            }
+
+            // Trailing dead cells don't need to be encoded before a row break.
+            if let Some(&(last_ch, _)) = runs.last() {
+                if last_ch == 'b' {
+                    runs.pop();
+                }
+            }
+
+            for (ch, count) in runs {
+                if count > 1 {
+                    body.push_str(&count.to_string());
+                }
+                body.push(ch);
+            }
+
+            if y + 1 < height {
+                body.push('$');
+            }
+        }
+        body.push('!');
+
+        return format!("x = {}, y = {}\n{}\n", width, height, body);
+    }
+}
+
+/// Parses the `x = .., y = ..[, rule = ..]` RLE header line.
+fn parse_rle_header(line: &str) -> Result<(i32, i32), PatternError> {
+    let mut width = None;
+    let mut height = None;
+
+    for part in line.split(',') {
+        let part = part.trim();
+        if part.starts_with('x') {
+            width = parse_header_value(part);
+        } else if part.starts_with('y') {
+            height = parse_header_value(part);
+        }
+    }
+
+    match (width, height) {
+        (Some(w), Some(h)) => Ok((w, h)),
+        _ => Err(PatternError::InvalidHeader),
+    }
+}
+
+fn parse_header_value(part: &str) -> Option<i32> {
+    let eq_index = part.find('=')?;
+    return part[(eq_index + 1)..].trim().parse::<i32>().ok();
+}
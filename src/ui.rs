@@ -5,6 +5,7 @@ use std::cell::RefCell;
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen::closure::Closure;
 use web_sys::{
+    window,
     Document,
     HtmlCanvasElement,
     CanvasRenderingContext2d
@@ -15,6 +16,9 @@ use web_sys::{
 pub struct Point {
     pub x: i32,
     pub y: i32,
+    /// The pointer button that triggered the event, following the DOM
+    /// `PointerEvent.button` numbering (0 = primary, 2 = secondary).
+    pub button: i16,
 }
 
 /// Used to respond the events sent from the DOM element.
@@ -22,6 +26,8 @@ pub trait Responder {
     fn on_mouse_down(&self, point: Point);
     fn on_mouse_move(&self, point: Point);
     fn on_mouse_up(&self);
+    fn on_key_down(&self, key: String);
+    fn on_resize(&self);
 }
 
 /// A wrapper of the [`Responder`] object.
@@ -47,10 +53,48 @@ impl ResponderHolder {
             responder.on_mouse_up();
         }
     }
+
+    fn on_key_down(&self, key: String) {
+        if let Some(responder) = self.responder.as_ref() {
+            responder.on_key_down(key);
+        }
+    }
+
+    fn on_resize(&self) {
+        if let Some(responder) = self.responder.as_ref() {
+            responder.on_resize();
+        }
+    }
+}
+
+/// Controls how the canvas's on-screen (CSS) size is derived from the world
+/// size and the viewport.
+#[derive(Copy, Clone)]
+pub enum ScaleMode {
+    /// Scale the world to fill the viewport, preserving aspect ratio.
+    Fit,
+    /// Use a fixed on-screen size (in CSS pixels) per cell.
+    Fixed(f64),
 }
 
-const DEFAULT_WIDTH: i32 = 500;
-const DEFAULT_HEIGHT: i32 = 500;
+/// The fraction of the viewport the world is allowed to occupy in
+/// [`ScaleMode::Fit`].
+const FIT_VIEWPORT_FRACTION: f64 = 0.9;
+
+/// The layout of the canvas. Shared via `Rc<RefCell<_>>` so the pointer event
+/// closures always see the size as of the last resize, not the size at the
+/// time they were installed.
+struct Layout {
+    /// Backing-store (device pixel) size, used for drawing.
+    width: f64,
+    height: f64,
+    cell_width: f64,
+    cell_height: f64,
+    /// CSS pixel cell size, used to hit-test `offsetX`/`offsetY`, which the
+    /// DOM reports in CSS pixels regardless of `devicePixelRatio`.
+    css_cell_width: f64,
+    css_cell_height: f64,
+}
 
 /// An object that acts as the controller of the canvas DOM elememnt.
 #[allow(dead_code)]
@@ -59,33 +103,38 @@ pub struct Canvas {
     ctx: CanvasRenderingContext2d,
     responder_holder: Rc<RefCell<ResponderHolder>>,
     event_handlers: LinkedList<Box<dyn Drop>>,
-    width: f64,
-    height: f64,
-    cell_width: f64,
-    cell_height: f64,
+    rows: i32,
+    cols: i32,
+    scale_mode: ScaleMode,
+    layout: Rc<RefCell<Layout>>,
 }
 
 impl Canvas {
-    /// Creates a new instance with the given [`web_sys::Document`] and world size.
-    /// 
+    /// Creates a new instance with the given [`web_sys::Document`], world
+    /// size and [`ScaleMode`].
+    ///
     /// Calling this method has a side-effect that manipulate the DOM to initialize
     /// the canvas and related elements.
-    pub fn new(document: &Document, rows: i32, cols: i32) -> Canvas {
-        let width = DEFAULT_WIDTH as f64;
-        let height = DEFAULT_HEIGHT as f64;
-        let cell_width = width / (rows as f64);
-        let cell_height = height / (cols as f64);
-
+    pub fn new(document: &Document, rows: i32, cols: i32, scale_mode: ScaleMode) -> Canvas {
         let body = document.body().unwrap();
-    
+
         let canvas_el = document.create_element("canvas")
             .unwrap()
             .dyn_into::<web_sys::HtmlCanvasElement>()
             .unwrap();
-        canvas_el.set_width(DEFAULT_WIDTH as u32);
-        canvas_el.set_height(DEFAULT_HEIGHT as u32);
         body.prepend_with_node_1(&canvas_el).unwrap();
 
+        let (width, height, cell_width, cell_height, css_cell_width, css_cell_height) =
+            apply_layout(&canvas_el, rows, cols, scale_mode);
+        let layout = Rc::new(RefCell::new(Layout {
+            width: width,
+            height: height,
+            cell_width: cell_width,
+            cell_height: cell_height,
+            css_cell_width: css_cell_width,
+            css_cell_height: css_cell_height,
+        }));
+
         // Install the event listeners.
         let responder_holder = Rc::new(RefCell::new(ResponderHolder {
             responder: None,
@@ -93,56 +142,112 @@ impl Canvas {
         let mut event_handlers: LinkedList<Box<dyn Drop>> = LinkedList::new();
         {
             let event_target: &web_sys::EventTarget = &canvas_el;
-            // The mouse down event:
+            // The pointer down event:
             {
                 let responder_holder_clone = responder_holder.clone();
-                let mouse_down_cb = 
-                Box::new(Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+                let canvas_el_clone = canvas_el.clone();
+                let layout_clone = layout.clone();
+                let pointer_down_cb =
+                Box::new(Closure::wrap(Box::new(move |event: web_sys::PointerEvent| {
+                    // Keep receiving move/up events for this pointer even if
+                    // it leaves the canvas bounds, so drags and touches work.
+                    let _ = canvas_el_clone.set_pointer_capture(event.pointer_id());
+
+                    let layout = layout_clone.borrow();
                     let point = Point {
-                        x: (event.offset_x() as f64 / cell_width) as i32,
-                        y: (event.offset_y() as f64 / cell_height) as i32,
+                        x: (event.offset_x() as f64 / layout.css_cell_width) as i32,
+                        y: (event.offset_y() as f64 / layout.css_cell_height) as i32,
+                        button: event.button(),
                     };
+                    drop(layout);
 
                     responder_holder_clone.borrow().on_mouse_down(point);
                 }) as Box<dyn FnMut(_)>));
                 event_target.add_event_listener_with_callback(
-                    "mousedown",
-                    mouse_down_cb.as_ref().as_ref().unchecked_ref()
+                    "pointerdown",
+                    pointer_down_cb.as_ref().as_ref().unchecked_ref()
                 ).unwrap();
-                event_handlers.push_back(mouse_down_cb);
+                event_handlers.push_back(pointer_down_cb);
             }
 
-            // The mouse move event:
+            // The pointer move event:
             {
                 let responder_holder_clone = responder_holder.clone();
-                let mouse_move_cb = 
-                Box::new(Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+                let layout_clone = layout.clone();
+                let pointer_move_cb =
+                Box::new(Closure::wrap(Box::new(move |event: web_sys::PointerEvent| {
+                    let layout = layout_clone.borrow();
                     let point = Point {
-                        x: (event.offset_x() as f64 / cell_width) as i32,
-                        y: (event.offset_y() as f64 / cell_height) as i32,
+                        x: (event.offset_x() as f64 / layout.css_cell_width) as i32,
+                        y: (event.offset_y() as f64 / layout.css_cell_height) as i32,
+                        button: event.button(),
                     };
+                    drop(layout);
 
                     responder_holder_clone.borrow().on_mouse_move(point);
                 }) as Box<dyn FnMut(_)>));
                 event_target.add_event_listener_with_callback(
-                    "mousemove",
-                    mouse_move_cb.as_ref().as_ref().unchecked_ref()
+                    "pointermove",
+                    pointer_move_cb.as_ref().as_ref().unchecked_ref()
                 ).unwrap();
-                event_handlers.push_back(mouse_move_cb);
+                event_handlers.push_back(pointer_move_cb);
             }
 
-            // The mouse up event:
+            // The pointer up event:
             {
                 let responder_holder_clone = responder_holder.clone();
-                let mouse_up_cb = 
+                let pointer_up_cb =
                 Box::new(Closure::wrap(Box::new(move || {
                     responder_holder_clone.borrow().on_mouse_up();
                 }) as Box<dyn FnMut()>));
                 event_target.add_event_listener_with_callback(
-                    "mouseup",
-                    mouse_up_cb.as_ref().as_ref().unchecked_ref()
+                    "pointerup",
+                    pointer_up_cb.as_ref().as_ref().unchecked_ref()
+                ).unwrap();
+                event_handlers.push_back(pointer_up_cb);
+            }
+
+            // The context menu event: suppressed so secondary-button erase
+            // drags don't pop the browser's right-click menu over the canvas.
+            {
+                let contextmenu_cb =
+                Box::new(Closure::wrap(Box::new(move |event: web_sys::Event| {
+                    event.prevent_default();
+                }) as Box<dyn FnMut(_)>));
+                event_target.add_event_listener_with_callback(
+                    "contextmenu",
+                    contextmenu_cb.as_ref().as_ref().unchecked_ref()
                 ).unwrap();
-                event_handlers.push_back(mouse_up_cb);
+                event_handlers.push_back(contextmenu_cb);
+            }
+
+            // The key down event, listened on the window so it works
+            // regardless of which element currently has focus:
+            {
+                let responder_holder_clone = responder_holder.clone();
+                let key_down_cb =
+                Box::new(Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+                    responder_holder_clone.borrow().on_key_down(event.key());
+                }) as Box<dyn FnMut(_)>));
+                window().unwrap().add_event_listener_with_callback(
+                    "keydown",
+                    key_down_cb.as_ref().as_ref().unchecked_ref()
+                ).unwrap();
+                event_handlers.push_back(key_down_cb);
+            }
+
+            // The window resize event:
+            {
+                let responder_holder_clone = responder_holder.clone();
+                let resize_cb =
+                Box::new(Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                    responder_holder_clone.borrow().on_resize();
+                }) as Box<dyn FnMut(_)>));
+                window().unwrap().add_event_listener_with_callback(
+                    "resize",
+                    resize_cb.as_ref().as_ref().unchecked_ref()
+                ).unwrap();
+                event_handlers.push_back(resize_cb);
             }
         }
 
@@ -158,7 +263,6 @@ impl Canvas {
         }
         {
             let style = canvas_el.style();
-            style.set_property("margin", "30px").unwrap();
             style.set_property("box-shadow", "0 10px 30px #00000026").unwrap();
             style.set_property("background-color", "#fff").unwrap();
         }
@@ -173,10 +277,10 @@ impl Canvas {
             ctx: context,
             responder_holder: responder_holder,
             event_handlers: event_handlers,
-            width: width,
-            height: height,
-            cell_width: cell_width,
-            cell_height: cell_height,
+            rows: rows,
+            cols: cols,
+            scale_mode: scale_mode,
+            layout: layout,
         };
     }
 
@@ -184,17 +288,96 @@ impl Canvas {
         self.responder_holder.borrow_mut().responder = Some(responder);
     }
 
+    /// Recomputes the backing store and CSS size of the canvas for the
+    /// current viewport and [`ScaleMode`]. Callers are expected to trigger a
+    /// full repaint afterwards, since resizing discards the canvas contents.
+    pub fn handle_resize(&self) {
+        let (width, height, cell_width, cell_height, css_cell_width, css_cell_height) =
+            apply_layout(&self.el, self.rows, self.cols, self.scale_mode);
+
+        let mut layout = self.layout.borrow_mut();
+        layout.width = width;
+        layout.height = height;
+        layout.cell_width = cell_width;
+        layout.cell_height = cell_height;
+        layout.css_cell_width = css_cell_width;
+        layout.css_cell_height = css_cell_height;
+    }
+
     pub fn clear(&self) {
-        self.ctx.clear_rect(0.0, 0.0, self.width, self.height);
+        let layout = self.layout.borrow();
+        self.ctx.clear_rect(0.0, 0.0, layout.width, layout.height);
     }
 
     pub fn draw_cell(&self, x: i32, y: i32) {
+        let layout = self.layout.borrow();
         self.ctx.set_fill_style(&JsValue::from_str("#000"));
         self.ctx.fill_rect(
-            self.cell_width * (x as f64),
-            self.cell_height * (y as f64),
-            self.cell_width,
-            self.cell_height
+            layout.cell_width * (x as f64),
+            layout.cell_height * (y as f64),
+            layout.cell_width,
+            layout.cell_height
+        );
+    }
+
+    /// Clears the rect occupied by a single cell, without touching the rest
+    /// of the canvas.
+    pub fn erase_cell(&self, x: i32, y: i32) {
+        let layout = self.layout.borrow();
+        self.ctx.clear_rect(
+            layout.cell_width * (x as f64),
+            layout.cell_height * (y as f64),
+            layout.cell_width,
+            layout.cell_height
         );
     }
 }
+
+/// Computes the on-screen (CSS pixel) size of the world for the given scale
+/// mode.
+fn compute_css_size(rows: i32, cols: i32, scale_mode: ScaleMode) -> (f64, f64) {
+    match scale_mode {
+        ScaleMode::Fixed(cell_size) => (cell_size * (rows as f64), cell_size * (cols as f64)),
+        ScaleMode::Fit => {
+            let win = window().unwrap();
+            let viewport_width = win.inner_width().unwrap().as_f64().unwrap();
+            let viewport_height = win.inner_height().unwrap().as_f64().unwrap();
+            let available_width = viewport_width * FIT_VIEWPORT_FRACTION;
+            let available_height = viewport_height * FIT_VIEWPORT_FRACTION;
+            let aspect_ratio = (rows as f64) / (cols as f64);
+
+            if available_width / aspect_ratio <= available_height {
+                (available_width, available_width / aspect_ratio)
+            } else {
+                (available_height * aspect_ratio, available_height)
+            }
+        }
+    }
+}
+
+/// Sizes the backing store to `css_size * devicePixelRatio` (so rendering
+/// stays crisp on HiDPI screens) and applies a matching CSS size, returning
+/// the new `(width, height, cell_width, cell_height, css_cell_width,
+/// css_cell_height)`. The first four are backing-store (device pixel) sizes
+/// for drawing; the last two are CSS pixel sizes, since DOM events like
+/// `offsetX`/`offsetY` are reported in CSS pixels regardless of
+/// `devicePixelRatio`.
+fn apply_layout(el: &HtmlCanvasElement, rows: i32, cols: i32, scale_mode: ScaleMode) -> (f64, f64, f64, f64, f64, f64) {
+    let (css_width, css_height) = compute_css_size(rows, cols, scale_mode);
+    let device_pixel_ratio = window().unwrap().device_pixel_ratio();
+
+    let width = css_width * device_pixel_ratio;
+    let height = css_height * device_pixel_ratio;
+
+    el.set_width(width.round() as u32);
+    el.set_height(height.round() as u32);
+
+    let style = el.style();
+    style.set_property("width", &format!("{}px", css_width)).unwrap();
+    style.set_property("height", &format!("{}px", css_height)).unwrap();
+
+    return (
+        width, height, width / (rows as f64), height / (cols as f64),
+        css_width / (rows as f64), css_height / (cols as f64)
+    );
+}